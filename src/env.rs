@@ -0,0 +1,89 @@
+//! Layer environment-variable overrides on top of a loaded configuration,
+//! following the `{NAME}_{FIELD}` convention used by tools such as cargo.
+
+use std::env;
+
+use toml::Value;
+use toml::value::Table;
+
+/// Build a `toml::Value` table of overrides from every environment variable
+/// prefixed with `{NAME}_` (matched case-insensitively against the
+/// upper-cased `name`), ready to be merged on top of a loaded configuration.
+///
+/// `SAMPLE_SERVER_PORT=8080` with `name` `"sample"` becomes the nested table
+/// `{ server = { port = 8080 } }`.
+pub fn overrides(name: &str) -> Value {
+    let prefix = format!("{}_", name.to_uppercase());
+    let mut table = Table::new();
+
+    for (key, value) in env::vars() {
+        if let Some(rest) = strip_prefix(&key, &prefix) {
+            let path: Vec<String> = rest.split('_').map(|part| part.to_lowercase()).collect();
+            insert(&mut table, &path, parse_scalar(&value));
+        }
+    }
+
+    Value::Table(table)
+}
+
+/// Case-insensitively strip `prefix` from `key`, if present.
+fn strip_prefix<'a>(key: &'a str, prefix: &str) -> Option<&'a str> {
+    if key.len() > prefix.len() && key[..prefix.len()].eq_ignore_ascii_case(prefix) {
+        Some(&key[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// Insert `value` into `table` at the nested key `path`, creating
+/// intermediate tables as needed.
+fn insert(table: &mut Table, path: &[String], value: Value) {
+    if path.len() == 1 {
+        table.insert(path[0].clone(), value);
+        return;
+    }
+
+    let entry = table
+        .entry(path[0].clone())
+        .or_insert_with(|| Value::Table(Table::new()));
+
+    if let Value::Table(ref mut nested) = *entry {
+        insert(nested, &path[1..], value);
+    }
+}
+
+/// Parse a raw environment variable value into the most specific TOML
+/// scalar it matches, falling back to a string.
+fn parse_scalar(value: &str) -> Value {
+    if let Ok(value) = value.parse::<i64>() {
+        Value::Integer(value)
+    } else if let Ok(value) = value.parse::<f64>() {
+        Value::Float(value)
+    } else if let Ok(value) = value.parse::<bool>() {
+        Value::Boolean(value)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::overrides;
+    use toml::Value;
+
+    #[test]
+    fn builds_nested_table_from_prefixed_vars() {
+        use std::env::set_var;
+
+        set_var("SAMPLE_SERVER_PORT", "8080");
+        set_var("SAMPLE_NAME", "test");
+        set_var("OTHER_IGNORED", "1");
+
+        let value = overrides("sample");
+        let expected: Value = toml::from_str(
+            "name = \"test\"\n[server]\nport = 8080\n",
+        ).unwrap();
+
+        assert_eq!(value, expected);
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
 use std::{io, error};
 use std::convert::From;
 
@@ -9,6 +10,16 @@ use toml;
 pub enum Error {
     File(io::Error),
     Deserialize(toml::de::Error),
+    #[cfg(feature = "json")]
+    DeserializeJson(::serde_json::Error),
+    #[cfg(feature = "yaml")]
+    DeserializeYaml(::serde_yaml::Error),
+    /// A configuration file, or a directory containing it, was writable by
+    /// someone other than its owner and so was refused by `try_load_guarded`.
+    Insecure(PathBuf),
+    /// Two files in the same directory could both plausibly be "the" config
+    /// file, so `try_load_strict` refused to silently pick one.
+    AmbiguousSource(PathBuf, PathBuf),
 }
 
 impl Display for Error {
@@ -22,13 +33,25 @@ impl error::Error for Error {
         match *self {
             Error::File(_) => "Error opening or reading file",
             Error::Deserialize(_) => "Error deserializing file",
+            #[cfg(feature = "json")]
+            Error::DeserializeJson(_) => "Error deserializing JSON file",
+            #[cfg(feature = "yaml")]
+            Error::DeserializeYaml(_) => "Error deserializing YAML file",
+            Error::Insecure(_) => "Configuration file or directory has insecure permissions",
+            Error::AmbiguousSource(_, _) => "Multiple candidate configuration files found",
         }
     }
 
-    fn cause(&self) -> Option<&error::Error> {
+    fn cause(&self) -> Option<&dyn error::Error> {
         match *self {
             Error::File(ref err) => Some(err),
             Error::Deserialize(ref err) => Some(err),
+            #[cfg(feature = "json")]
+            Error::DeserializeJson(ref err) => Some(err),
+            #[cfg(feature = "yaml")]
+            Error::DeserializeYaml(ref err) => Some(err),
+            Error::Insecure(_) => None,
+            Error::AmbiguousSource(_, _) => None,
         }
     }
 }
@@ -44,3 +67,17 @@ impl From<toml::de::Error> for Error {
         Error::Deserialize(err)
     }
 }
+
+#[cfg(feature = "json")]
+impl From<::serde_json::Error> for Error {
+    fn from(err: ::serde_json::Error) -> Error {
+        Error::DeserializeJson(err)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<::serde_yaml::Error> for Error {
+    fn from(err: ::serde_yaml::Error) -> Error {
+        Error::DeserializeYaml(err)
+    }
+}
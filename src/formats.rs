@@ -0,0 +1,42 @@
+//! Dispatches (de)serialization across the TOML, JSON, and YAML backends,
+//! selecting one based on a file's extension.
+//!
+//! JSON and YAML support are opt-in cargo features (`json` and `yaml`); with
+//! neither enabled only TOML is recognised, matching the crate's original
+//! behaviour.
+
+use serde::de::DeserializeOwned;
+
+use error::Error;
+
+/// File extensions that `path_list` should generate and `from_str` knows how
+/// to parse, in the order they should be tried.
+pub fn extensions() -> Vec<&'static str> {
+    // `mut` is only needed when a feature below pushes onto the list.
+    #[cfg_attr(not(any(feature = "json", feature = "yaml")), allow(unused_mut))]
+    let mut extensions = vec!["toml"];
+
+    #[cfg(feature = "json")]
+    extensions.push("json");
+
+    #[cfg(feature = "yaml")]
+    extensions.push("yaml");
+    #[cfg(feature = "yaml")]
+    extensions.push("yml");
+
+    extensions
+}
+
+/// Deserialize `text` using the backend matching `extension`.
+///
+/// An unrecognised or missing extension falls back to TOML, so paths such as
+/// `~/.config/{name}/config` (which carry no extension) keep working.
+pub fn from_str<C: DeserializeOwned>(text: &str, extension: Option<&str>) -> Result<C, Error> {
+    match extension {
+        #[cfg(feature = "json")]
+        Some("json") => Ok(::serde_json::from_str(text)?),
+        #[cfg(feature = "yaml")]
+        Some("yaml") | Some("yml") => Ok(::serde_yaml::from_str(text)?),
+        _ => Ok(::toml::from_str(text)?),
+    }
+}
@@ -12,14 +12,53 @@
 //! 1. `./.{name}.toml`
 //! 1. `~/.{name}`
 //! 1. `~/.{name}.toml`
-//! 1. `~/.config/{name}`
-//! 1. `~/.config/{name}.toml`
-//! 1. `~/.config/{name}/config`
-//! 1. `~/.config/{name}/config.toml`
-//! 1. `/etc/.config/{name}`
-//! 1. `/etc/.config/{name}.toml`
-//! 1. `/etc/.config/{name}/config`
-//! 1. `/etc/.config/{name}/config.toml`
+//! 1. `{config_dir}/{name}`
+//! 1. `{config_dir}/{name}.toml`
+//! 1. `{config_dir}/{name}/config`
+//! 1. `{config_dir}/{name}/config.toml`
+//! 1. `/etc/{name}`
+//! 1. `/etc/{name}.toml`
+//! 1. `/etc/{name}/config`
+//! 1. `/etc/{name}/config.toml`
+//!
+//! `{config_dir}` is resolved per-platform via the `dirs` crate (honouring
+//! `XDG_CONFIG_HOME` on Linux, for example), and the full list for a given
+//! name can be inspected with the public `path_list` function.
+//!
+//! Each entry above is tried as a TOML file by default; with the `json` or
+//! `yaml` cargo features enabled, `.json`, `.yaml`, and `.yml` variants of
+//! every entry are searched too, and parsed with the backend matching the
+//! extension that was found.
+//!
+//! `load`/`try_load` use the first file found. `load_merged`/`try_load_merged`
+//! instead read every file found in the list and merge them together,
+//! lowest-priority first, so a system-wide file can set defaults that a
+//! user's file only partially overrides. In merged mode, `{config_dir}/{name}/conf.d/`
+//! and `/etc/{name}/conf.d/` are also scanned, and every recognised file
+//! inside is merged on top of the corresponding main config, in lexical
+//! filename order.
+//!
+//! `load_env`/`try_load_env` load as `load`/`try_load` do, then override any
+//! fields set by an environment variable named `{NAME}_{FIELD}` (uppercased,
+//! with `_` separating nested keys), e.g. `SAMPLE_SERVER_PORT` overrides
+//! `server.port` when loading with the name `"sample"`.
+//!
+//! `load_guarded`/`try_load_guarded` load as `load`/`try_load` do, but refuse
+//! to read a file that is group- or other-writable, or that sits under a
+//! directory that is, since a writable config is a privilege-escalation
+//! vector for daemons running as another user. This check is a no-op on
+//! non-Unix platforms.
+//!
+//! `load_strict`/`try_load_strict` load as `load`/`try_load` do, but error
+//! instead of silently preferring one file when two files in the same
+//! directory could both plausibly be "the" config (e.g. both `./{name}` and
+//! `./{name}.toml`), so the ambiguity has to be resolved by hand.
+//!
+//! `load_or_create`/`try_load_or_create` (for `C: Serialize`) load as
+//! `load`/`try_load` do, but if no file is found they write the `Default`
+//! configuration as TOML to the first writable location in the search order,
+//! creating parent directories as needed, so first-run users get a starting
+//! point on disk instead of an invisible default.
 //!
 //! # Example Usage
 //!
@@ -59,18 +98,30 @@ extern crate serde;
 #[allow(unused_imports)]
 #[macro_use]
 extern crate serde_derive;
-#[allow(unused_imports)]
+#[cfg(test)]
 extern crate tempdir;
 extern crate toml;
-
+extern crate dirs;
+#[cfg(feature = "json")]
+extern crate serde_json;
+#[cfg(feature = "yaml")]
+extern crate serde_yaml;
+#[cfg(unix)]
+extern crate libc;
+
+mod env;
 mod error;
+mod formats;
+mod merge;
+mod mistrust;
 
 pub use error::Error;
+use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::default::Default;
-use std::env::home_dir;
-use std::fs::File;
-use std::io::Read;
+use std::ffi::OsStr;
+use std::fs::{create_dir_all, read_dir, File};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
 /// Load a struct from a configuration file.
@@ -106,6 +157,117 @@ pub trait Load: Sized {
     /// Loads the configuration from the given path or falls back to search if
     /// the path is None. Errors if file can't be read or deserialized.
     fn try_fallback_load<S: AsRef<str>, P: AsRef<Path>>(filename: S, path: Option<P>) -> Result<Self, Error>;
+
+    /// Find every configuration file in the search list and merge them
+    /// together, lowest-priority first, on top of the `Default`, falling
+    /// back to the default for any field not set in any file.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if there are any issues reading, parsing, or merging any
+    /// of the files. To catch these errors, use `try_load_merged` instead.
+    fn load_merged<S: AsRef<str>>(filename: S) -> Self
+    where
+        Self: Serialize,
+    {
+        Load::try_load_merged(filename).expect("Error reading configuration from file")
+    }
+
+    /// Find every configuration file in the search list and merge them
+    /// together, lowest-priority first, on top of the `Default`, falling
+    /// back to the default for any field not set in any file. Errors if any
+    /// file can't be read, parsed, or merged.
+    fn try_load_merged<S: AsRef<str>>(filename: S) -> Result<Self, Error>
+    where
+        Self: Serialize;
+
+    /// Find a configuration file (as with `load`), then override any fields
+    /// set by an environment variable following the `{NAME}_{FIELD}`
+    /// convention, e.g. `SAMPLE_SERVER_PORT` overrides `server.port` when
+    /// loading with `filename` `"sample"`.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if there are any issues reading, parsing, or
+    /// deserializing. To catch these errors, use `try_load_env` instead.
+    fn load_env<S: AsRef<str>>(filename: S) -> Self
+    where
+        Self: Serialize,
+    {
+        Load::try_load_env(filename).expect("Error reading configuration from file")
+    }
+
+    /// Find a configuration file (as with `load`), then override any fields
+    /// set by an environment variable following the `{NAME}_{FIELD}`
+    /// convention. Fields left unset by both the file and the environment
+    /// fall back to the `Default`. Errors if the file can't be read, parsed,
+    /// or deserialized.
+    fn try_load_env<S: AsRef<str>>(filename: S) -> Result<Self, Error>
+    where
+        Self: Serialize;
+
+    /// Find a configuration file (as with `load`), refusing to read it if it,
+    /// or a directory containing it, is writable by anyone other than its
+    /// owner.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if there are any issues reading or deserializing the
+    /// file, or if the file's permissions can't be trusted. To catch these
+    /// errors, use `try_load_guarded` instead.
+    fn load_guarded<S: AsRef<str>>(filename: S) -> Self {
+        Load::try_load_guarded(filename).expect("Error reading configuration from file")
+    }
+
+    /// Find a configuration file (as with `load`), refusing to read it if it,
+    /// or a directory containing it, is writable by anyone other than its
+    /// owner. Errors with `Error::Insecure` in that case; this check is a
+    /// no-op on non-Unix platforms.
+    fn try_load_guarded<S: AsRef<str>>(filename: S) -> Result<Self, Error>;
+
+    /// Find a configuration file (as with `load`), but error instead of
+    /// silently picking one if two files in the same directory both exist
+    /// and could plausibly be "the" config for `filename`.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if there are any issues reading or deserializing the
+    /// file, or if the search list is ambiguous. To catch these errors, use
+    /// `try_load_strict` instead.
+    fn load_strict<S: AsRef<str>>(filename: S) -> Self {
+        Load::try_load_strict(filename).expect("Error reading configuration from file")
+    }
+
+    /// Find a configuration file (as with `load`), but return
+    /// `Error::AmbiguousSource` instead of silently picking one if two files
+    /// in the same directory both exist and could plausibly be "the" config
+    /// for `filename`.
+    fn try_load_strict<S: AsRef<str>>(filename: S) -> Result<Self, Error>;
+
+    /// Find a configuration file (as with `load`), or if none exists, write
+    /// the `Default` configuration as TOML to the first writable location in
+    /// the search order and return it.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if there are any issues reading, deserializing, or
+    /// writing the file. To catch these errors, use `try_load_or_create`
+    /// instead.
+    fn load_or_create<S: AsRef<str>>(filename: S) -> Self
+    where
+        Self: Serialize,
+    {
+        Load::try_load_or_create(filename).expect("Error reading configuration from file")
+    }
+
+    /// Find a configuration file (as with `load`), or if none exists, write
+    /// the `Default` configuration as TOML to the first writable location in
+    /// the search order (creating parent directories as needed) and return
+    /// it. Errors reading, writing, or creating directories are folded into
+    /// `Error::File`.
+    fn try_load_or_create<S: AsRef<str>>(filename: S) -> Result<Self, Error>
+    where
+        Self: Serialize;
 }
 
 impl<C> Load for C
@@ -118,79 +280,340 @@ where
         } else {
             let paths = path_list(filename.as_ref());
 
-            match paths.iter().find(|p| p.exists()) {
+            match find_existing(&paths) {
                 Some(path) => read_from_file(path),
                 None => Ok(Default::default()),
             }
         }
     }
+
+    fn try_load_merged<S: AsRef<str>>(filename: S) -> Result<C, Error>
+    where
+        C: Serialize,
+    {
+        let paths = merge_file_list(filename.as_ref());
+        let mut merged = default_value(&C::default())?;
+
+        // `merge_file_list` is already ordered lowest-priority first, so
+        // later files win.
+        for path in paths.iter().filter(|p| p.is_file()) {
+            merge::merge(&mut merged, read_value_from_file(path)?);
+        }
+
+        Ok(merged.try_into()?)
+    }
+
+    fn try_load_env<S: AsRef<str>>(filename: S) -> Result<C, Error>
+    where
+        C: Serialize,
+    {
+        let paths = path_list(filename.as_ref());
+        let mut value = default_value(&C::default())?;
+
+        if let Some(path) = find_existing(&paths) {
+            merge::merge(&mut value, read_value_from_file(path)?);
+        }
+
+        merge::merge(&mut value, env::overrides(filename.as_ref()));
+
+        Ok(value.try_into()?)
+    }
+
+    fn try_load_guarded<S: AsRef<str>>(filename: S) -> Result<C, Error> {
+        let paths = path_list(filename.as_ref());
+
+        match find_existing(&paths) {
+            Some(path) => {
+                mistrust::check(path)?;
+                read_from_file(path)
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    fn try_load_strict<S: AsRef<str>>(filename: S) -> Result<C, Error> {
+        let mut found = None;
+
+        for (_, group) in group_by_parent(path_list(filename.as_ref())) {
+            let mut existing = group.into_iter().filter(|p| p.is_file());
+
+            if let Some(first) = existing.next() {
+                if let Some(second) = existing.next() {
+                    return Err(Error::AmbiguousSource(first, second));
+                }
+
+                if found.is_none() {
+                    found = Some(first);
+                }
+            }
+        }
+
+        match found {
+            Some(path) => read_from_file(path),
+            None => Ok(Default::default()),
+        }
+    }
+
+    fn try_load_or_create<S: AsRef<str>>(filename: S) -> Result<C, Error>
+    where
+        C: Serialize,
+    {
+        let paths = path_list(filename.as_ref());
+
+        if let Some(path) = find_existing(&paths) {
+            return read_from_file(path);
+        }
+
+        let default = C::default();
+        let mut last_err = None;
+
+        for path in &paths {
+            match write_default(path, &default) {
+                Ok(()) => {
+                    last_err = None;
+                    break;
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        match last_err {
+            Some(err) => Err(err),
+            None => Ok(default),
+        }
+    }
+}
+
+/// Find the first path in `paths` that is a regular file, skipping any that
+/// are missing or that are directories (as `{config_dir}/{name}` is whenever
+/// its `conf.d` drop-in directory exists).
+fn find_existing(paths: &[PathBuf]) -> Option<&PathBuf> {
+    paths.iter().find(|p| p.is_file())
 }
 
-/// Read a configuration from a file.
+/// Read a configuration from a file, dispatching on its extension to select
+/// the TOML, JSON, or YAML backend.
 fn read_from_file<P, C>(path: P) -> Result<C, Error>
 where
     P: AsRef<Path>,
     C: Default + DeserializeOwned,
 {
+    let path = path.as_ref();
     let mut text = String::new();
     File::open(path)?.read_to_string(&mut text)?;
-    Ok(toml::from_str(&text)?)
+    formats::from_str(&text, path.extension().and_then(OsStr::to_str))
 }
 
-/// Generate a vector of all the paths to search for a configuration file.
-fn path_list(name: &str) -> Vec<PathBuf> {
-    let mut paths = Vec::new();
-
-    // Add relative paths
-    let mut relative_paths = vec![
-        format!("{}", name),
-        format!("{}.toml", name),
-        format!(".{}", name),
-        format!(".{}.toml", name),
-    ];
-    paths.append(&mut relative_paths);
-
-    // Get the home directory as a string.
-    let home = home_dir()
+/// Write `value` as TOML to `path`, creating any parent directories first.
+/// Serialization failures are folded into `Error::File` alongside I/O
+/// failures, since both mean the default couldn't be written to this path.
+fn write_default<C, P>(path: P, value: &C) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    C: Serialize,
+{
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    let text = toml::to_string_pretty(value).map_err(io::Error::other)?;
+
+    File::create(path)?.write_all(text.as_bytes())?;
+
+    Ok(())
+}
+
+/// Serialize `value` to a `toml::Value`, for use as the base layer of a
+/// merge. Serialization failures are folded into `Error::File`, as with
+/// `write_default`.
+fn default_value<C: Serialize>(value: &C) -> Result<toml::Value, Error> {
+    toml::Value::try_from(value).map_err(|err| io::Error::other(err).into())
+}
+
+/// Read a raw TOML value from a file, without deserializing into a concrete
+/// type. Used to merge several files together before a final deserialize.
+fn read_value_from_file<P: AsRef<Path>>(path: P) -> Result<toml::Value, Error> {
+    let path = path.as_ref();
+    let mut text = String::new();
+    File::open(path)?.read_to_string(&mut text)?;
+    formats::from_str(&text, path.extension().and_then(OsStr::to_str))
+}
+
+/// Generate `base` and `base.{ext}` for every supported format extension, in
+/// the order they should be tried.
+fn with_extensions(base: &str) -> Vec<String> {
+    let mut variants = vec![base.to_string()];
+
+    for extension in formats::extensions() {
+        variants.push(format!("{}.{}", base, extension));
+    }
+
+    variants
+}
+
+/// Get the user's home directory as a string, if one can be resolved.
+fn home_string() -> Option<String> {
+    dirs::home_dir()
         .map(|h| h.into_os_string())
-        .and_then(|p| p.into_string().ok());
-
-    // Add home paths
-    let mut home_paths = match home {
-        Some(home) => {
-            vec![
-                format!("{}/.{}", home, name),
-                format!("{}/.{}.toml", home, name),
-                format!("{}/.config/{}", home, name),
-                format!("{}/.config/{}.toml", home, name),
-                format!("{}/.config/{}/config", home, name),
-                format!("{}/.config/{}/config.toml", home, name),
-            ]
-        }
-        None => vec![],
-    };
-    paths.append(&mut home_paths);
+        .and_then(|p| p.into_string().ok())
+}
 
-    // Add absolute paths
-    let mut absolute_paths = vec![
-        format!("/etc/.config/{}", name),
-        format!("/etc/.config/{}.toml", name),
-        format!("/etc/.config/{}/config", name),
-        format!("/etc/.config/{}/config.toml", name),
-    ];
-    paths.append(&mut absolute_paths);
+/// Get the platform (and `XDG_CONFIG_HOME`-aware) user config directory as a
+/// string, if one can be resolved.
+fn config_dir_string() -> Option<String> {
+    dirs::config_dir()
+        .map(|d| d.into_os_string())
+        .and_then(|p| p.into_string().ok())
+}
 
-    paths
+/// Base paths (without extension) relative to the current directory.
+fn relative_bases(name: &str) -> Vec<String> {
+    vec![name.to_string(), format!(".{}", name)]
+}
+
+/// Base paths (without extension) under the user's home and config
+/// directories.
+fn home_bases(name: &str) -> Vec<String> {
+    let mut bases = Vec::new();
+
+    if let Some(home) = home_string() {
+        bases.push(format!("{}/.{}", home, name));
+    }
+
+    if let Some(config) = config_dir_string() {
+        bases.push(format!("{}/{}", config, name));
+        bases.push(format!("{}/{}/config", config, name));
+    }
+
+    bases
+}
+
+/// Base paths (without extension) under `/etc`.
+fn etc_bases(name: &str) -> Vec<String> {
+    vec![
+        format!("/etc/{}", name),
+        format!("/etc/{}/config", name),
+    ]
+}
+
+/// The `conf.d` drop-in directory for the user's config directory, if one
+/// can be resolved.
+fn home_conf_d(name: &str) -> Option<PathBuf> {
+    config_dir_string().map(|config| PathBuf::from(format!("{}/{}/conf.d", config, name)))
+}
+
+/// The `conf.d` drop-in directory under `/etc`.
+fn etc_conf_d(name: &str) -> PathBuf {
+    PathBuf::from(format!("/etc/{}/conf.d", name))
+}
+
+/// Generate a vector of all the paths to search for a configuration file,
+/// highest-priority first. Exposed so callers can log which paths were
+/// searched.
+pub fn path_list(name: &str) -> Vec<PathBuf> {
+    let mut bases = relative_bases(name);
+
+    bases.append(&mut home_bases(name));
+    bases.append(&mut etc_bases(name));
+
+    bases
         .into_iter()
+        .flat_map(|base| with_extensions(&base))
         .map(|p| AsRef::<Path>::as_ref(&p).to_path_buf())
         .collect()
 }
 
+/// Generate the ordered list of files to merge for `try_load_merged`, from
+/// lowest to highest priority: system-wide files and their `conf.d`
+/// fragments, then the user's files and fragments, then the files relative
+/// to the current directory.
+fn merge_file_list(name: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    paths.extend(
+        etc_bases(name)
+            .into_iter()
+            .rev()
+            .flat_map(|base| with_extensions(&base).into_iter().rev())
+            .map(PathBuf::from),
+    );
+    paths.extend(conf_d_files(etc_conf_d(name)));
+
+    paths.extend(
+        home_bases(name)
+            .into_iter()
+            .rev()
+            .flat_map(|base| with_extensions(&base).into_iter().rev())
+            .map(PathBuf::from),
+    );
+    if let Some(conf_d) = home_conf_d(name) {
+        paths.extend(conf_d_files(conf_d));
+    }
+
+    paths.extend(
+        relative_bases(name)
+            .into_iter()
+            .rev()
+            .flat_map(|base| with_extensions(&base).into_iter().rev())
+            .map(PathBuf::from),
+    );
+
+    paths
+}
+
+/// Group `paths` by parent directory, preserving the order directories are
+/// first seen in. Used by `try_load_strict` to find candidates that could
+/// ambiguously both be "the" config for a directory.
+fn group_by_parent(paths: Vec<PathBuf>) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    let mut groups: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+
+    for path in paths {
+        let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+
+        match groups.iter_mut().find(|group| group.0 == dir) {
+            Some(group) => group.1.push(path),
+            None => groups.push((dir, vec![path])),
+        }
+    }
+
+    groups
+}
+
+/// List the files in a `conf.d` drop-in directory that should be merged on
+/// top of the main configuration, sorted by file name so fragments can be
+/// ordered by naming them accordingly. A missing directory yields an empty
+/// list.
+fn conf_d_files<P: AsRef<Path>>(dir: P) -> Vec<PathBuf> {
+    let extensions = formats::extensions();
+
+    let entries = match read_dir(dir.as_ref()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(OsStr::to_str)
+                .map(|extension| extensions.contains(&extension))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    files.sort();
+    files
+}
+
 #[cfg(test)]
 mod test {
 
     /// Sample configuration
-    #[derive(Debug, PartialEq, Eq, Deserialize)]
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
     struct Config {
         /// Sample variable
         var: String,
@@ -209,6 +632,7 @@ mod test {
         use std::path::{Path, PathBuf};
 
         set_var("HOME", "/home/test");
+        set_var("XDG_CONFIG_HOME", "/home/test/.config");
         let paths = super::path_list("testcfg");
 
         let expected: Vec<PathBuf> = vec![
@@ -222,10 +646,10 @@ mod test {
             "/home/test/.config/testcfg.toml",
             "/home/test/.config/testcfg/config",
             "/home/test/.config/testcfg/config.toml",
-            "/etc/.config/testcfg",
-            "/etc/.config/testcfg.toml",
-            "/etc/.config/testcfg/config",
-            "/etc/.config/testcfg/config.toml",
+            "/etc/testcfg",
+            "/etc/testcfg.toml",
+            "/etc/testcfg/config",
+            "/etc/testcfg/config.toml",
         ].into_iter()
             .map(|p| AsRef::<Path>::as_ref(&p).to_path_buf())
             .collect();
@@ -242,6 +666,42 @@ mod test {
         assert_eq!(config, Config::default());
     }
 
+    /// Test that a file in the `conf.d` drop-in directory under the user's
+    /// config directory is merged on top of the default configuration.
+    #[test]
+    fn load_merged_conf_d_test() {
+        use std::env::{set_current_dir, set_var};
+        use std::fs::{create_dir_all, OpenOptions};
+        use std::io::Write;
+        use super::Load;
+        use tempdir::TempDir;
+
+        let temp_dir = TempDir::new("loadcfg-test")
+            .expect("Could not create temporary directory for test");
+        set_current_dir(temp_dir.path())
+            .expect("Could not change into temporary directory for test");
+
+        let home = temp_dir.path().join("home");
+        set_var("HOME", &home);
+        set_var("XDG_CONFIG_HOME", home.join(".config"));
+
+        let conf_d = home.join(".config/mergecfg/conf.d");
+        create_dir_all(&conf_d)
+            .expect("Could not create conf.d directory for test");
+
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(conf_d.join("10-override.toml"))
+            .expect("Couldn't open drop-in configuration file.")
+            .write_all("var = \"Overridden by conf.d\"\n".as_bytes())
+            .expect("Couldn't write drop-in configuration file.");
+
+        let config = Config::load_merged("mergecfg");
+        let expected = Config { var: "Overridden by conf.d".to_string() };
+        assert_eq!(config, expected);
+    }
+
     /// Test load configuration from a file.
     #[test]
     fn file_test() {
@@ -272,6 +732,144 @@ mod test {
         assert_eq!(config, expected);
     }
 
+    /// Test loading configuration from a JSON file, selected by extension.
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_file_test() {
+        use std::env::set_current_dir;
+        use std::fs::OpenOptions;
+        use std::io::Write;
+        use super::Load;
+        use tempdir::TempDir;
+
+        let temp_dir = TempDir::new("loadcfg-test")
+            .expect("Could not create temporary directory for test");
+        set_current_dir(temp_dir.path())
+            .expect("Could not change into temporary directory for test");
+
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(".testcfg.json")
+            .expect("Couldn't open test configuration file.")
+            .write_all("{\"var\": \"Test configuration file\"}".as_bytes())
+            .expect("Couldn't write test configuration file.");
+
+        let config = Config::load("testcfg");
+        let expected = Config { var: "Test configuration file".to_string() };
+        assert_eq!(config, expected);
+    }
+
+    /// Test that an environment variable overrides a value loaded from the
+    /// default configuration.
+    #[test]
+    fn load_env_test() {
+        use std::env::{set_current_dir, set_var};
+        use super::Load;
+        use tempdir::TempDir;
+
+        let temp_dir = TempDir::new("loadcfg-test")
+            .expect("Could not create temporary directory for test");
+        set_current_dir(temp_dir.path())
+            .expect("Could not change into temporary directory for test");
+
+        set_var("ENVCFG_VAR", "Overridden by environment");
+
+        let config = Config::load_env("envcfg");
+        let expected = Config { var: "Overridden by environment".to_string() };
+        assert_eq!(config, expected);
+    }
+
+    /// Test that a group-writable configuration file is refused.
+    #[cfg(unix)]
+    #[test]
+    fn load_guarded_rejects_insecure_file() {
+        use std::env::set_current_dir;
+        use std::fs::{set_permissions, OpenOptions, Permissions};
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+        use super::Load;
+        use tempdir::TempDir;
+
+        let temp_dir = TempDir::new("loadcfg-test")
+            .expect("Could not create temporary directory for test");
+        set_current_dir(temp_dir.path())
+            .expect("Could not change into temporary directory for test");
+
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(".testcfg.toml")
+            .expect("Couldn't open test configuration file.")
+            .write_all("var = \"Test configuration file\"\n".as_bytes())
+            .expect("Couldn't write test configuration file.");
+
+        set_permissions(".testcfg.toml", Permissions::from_mode(0o666))
+            .expect("Couldn't set insecure permissions on test configuration file.");
+
+        let result = Config::try_load_guarded("testcfg");
+        match result {
+            Err(super::Error::Insecure(_)) => (),
+            other => panic!("Expected Error::Insecure, got {:?}", other),
+        }
+    }
+
+    /// Test that two plausible config files in the same directory are
+    /// reported as ambiguous instead of one being silently preferred.
+    #[test]
+    fn load_strict_detects_ambiguous_source() {
+        use std::env::set_current_dir;
+        use std::fs::OpenOptions;
+        use std::io::Write;
+        use super::Load;
+        use tempdir::TempDir;
+
+        let temp_dir = TempDir::new("loadcfg-test")
+            .expect("Could not create temporary directory for test");
+        set_current_dir(temp_dir.path())
+            .expect("Could not change into temporary directory for test");
+
+        for name in &["testcfg", "testcfg.toml"] {
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .open(name)
+                .expect("Couldn't open test configuration file.")
+                .write_all("var = \"Test configuration file\"\n".as_bytes())
+                .expect("Couldn't write test configuration file.");
+        }
+
+        let result = Config::try_load_strict("testcfg");
+        match result {
+            Err(super::Error::AmbiguousSource(_, _)) => (),
+            other => panic!("Expected Error::AmbiguousSource, got {:?}", other),
+        }
+    }
+
+    /// Test that a missing configuration file is created with the default
+    /// contents on first load.
+    #[test]
+    fn load_or_create_test() {
+        use std::env::set_current_dir;
+        use std::fs::read_to_string;
+        use super::Load;
+        use tempdir::TempDir;
+
+        let temp_dir = TempDir::new("loadcfg-test")
+            .expect("Could not create temporary directory for test");
+        set_current_dir(temp_dir.path())
+            .expect("Could not change into temporary directory for test");
+
+        let config = Config::load_or_create("testcfg");
+        assert_eq!(config, Config::default());
+
+        let written = read_to_string("testcfg")
+            .expect("Expected default configuration to have been written to disk");
+        let reloaded: Config = toml::from_str(&written)
+            .expect("Written configuration was not valid TOML");
+        assert_eq!(reloaded, Config::default());
+    }
+
     /// Test load configuration from a file specified directly.
     #[test]
     fn specified_file_test() {
@@ -0,0 +1,71 @@
+//! Deep merging of TOML values, used to layer several configuration files
+//! on top of one another.
+
+use toml::Value;
+
+/// Merge `overlay` into `base` in place.
+///
+/// When both sides are tables, the merge recurses key-by-key so that nested
+/// tables are combined rather than replaced. Any other value in `overlay`
+/// (including arrays and scalars) overwrites the corresponding value in
+/// `base` wholesale.
+pub fn merge(base: &mut Value, overlay: Value) {
+    match overlay {
+        Value::Table(overlay_table) => {
+            if let Value::Table(ref mut base_table) = *base {
+                for (key, value) in overlay_table {
+                    match base_table.get_mut(&key) {
+                        Some(existing) => merge(existing, value),
+                        None => {
+                            base_table.insert(key, value);
+                        }
+                    }
+                }
+                return;
+            }
+            *base = Value::Table(overlay_table);
+        }
+        other => *base = other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::merge;
+    use toml::Value;
+
+    #[test]
+    fn scalars_are_overwritten() {
+        let mut base: Value = toml::from_str("a = 1\nb = 2\n").unwrap();
+        let overlay: Value = toml::from_str("b = 3\n").unwrap();
+
+        merge(&mut base, overlay);
+
+        assert_eq!(base, toml::from_str("a = 1\nb = 3\n").unwrap());
+    }
+
+    #[test]
+    fn tables_are_merged_recursively() {
+        let mut base: Value = toml::from_str(
+            "[server]\nhost = \"localhost\"\nport = 80\n",
+        ).unwrap();
+        let overlay: Value = toml::from_str("[server]\nport = 8080\n").unwrap();
+
+        merge(&mut base, overlay);
+
+        let expected: Value = toml::from_str(
+            "[server]\nhost = \"localhost\"\nport = 8080\n",
+        ).unwrap();
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn arrays_are_replaced_wholesale() {
+        let mut base: Value = toml::from_str("values = [1, 2, 3]\n").unwrap();
+        let overlay: Value = toml::from_str("values = [4]\n").unwrap();
+
+        merge(&mut base, overlay);
+
+        assert_eq!(base, toml::from_str("values = [4]\n").unwrap());
+    }
+}
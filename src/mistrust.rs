@@ -0,0 +1,59 @@
+//! Guarded loading that refuses to read a configuration file that is
+//! writable by anyone other than its owner, or that sits under a directory
+//! that is, since a writable config is a privilege-escalation vector for
+//! daemons. Modelled on Arti's `fs_mistrust`.
+
+use std::path::Path;
+
+use error::Error;
+
+/// Check that `path`, and every directory containing it, is trustworthy: not
+/// group- or other-writable, and owned by the current user or root.
+///
+/// This is a no-op on non-Unix platforms, where there is no portable way to
+/// inspect file ownership and permission bits.
+#[cfg(unix)]
+pub fn check<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let uid = unsafe { ::libc::getuid() };
+
+    // Resolve a relative path against the current directory so that the
+    // directories containing it are checked too.
+    let path = path.as_ref();
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        ::std::env::current_dir()?.join(path)
+    };
+
+    for ancestor in absolute.ancestors() {
+        check_one(ancestor, uid)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn check<P: AsRef<Path>>(_path: P) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn check_one(path: &Path, uid: ::libc::uid_t) -> Result<(), Error> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = match path.metadata() {
+        Ok(metadata) => metadata,
+        // An ancestor that doesn't exist (or can't be stat'd) can't be
+        // insecure.
+        Err(_) => return Ok(()),
+    };
+
+    let group_or_other_writable = metadata.mode() & 0o022 != 0;
+    let trusted_owner = metadata.uid() == uid || metadata.uid() == 0;
+
+    if group_or_other_writable || !trusted_owner {
+        Err(Error::Insecure(path.to_path_buf()))
+    } else {
+        Ok(())
+    }
+}